@@ -0,0 +1,134 @@
+extern crate bincode;
+extern crate pyo3;
+extern crate sha3;
+
+use pyo3::prelude::*;
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::nohash::{identifier_map_with_capacity, IdentifierMap};
+
+/// Compute a hex SHA3-256 digest over a canonical serialization of
+/// `(counts, degree, measure, subsystems_size)`, so a cache hit fully
+/// determines the inputs that produced it.
+pub fn digest_hex(
+    counts: &[HashMap<String, i32>],
+    bitstring_range: (i32, i32),
+    actual_measure: (i32, i32),
+    subsystems_size: i32,
+) -> String {
+    let mut hasher = Sha3_256::new();
+    for data in counts {
+        let mut entries: Vec<(&String, &i32)> = data.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (bit_string, count) in entries {
+            hasher.update(bit_string.as_bytes());
+            hasher.update(count.to_le_bytes());
+        }
+        hasher.update(b"|");
+    }
+    hasher.update(bitstring_range.0.to_le_bytes());
+    hasher.update(bitstring_range.1.to_le_bytes());
+    hasher.update(actual_measure.0.to_le_bytes());
+    hasher.update(actual_measure.1.to_le_bytes());
+    hasher.update(subsystems_size.to_le_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Like `digest_hex`, but keyed on `(shots, selected_classical_registers,
+/// counts fingerprint)` for the `*_2_rust` family, which selects its
+/// subsystem via an explicit register list instead of a `(range, measure)`
+/// pair. `counts_groups` takes one slice per independent count table (e.g.
+/// `overlap_echo_core_2_rust`'s `first_counts`/`second_counts`) so callers
+/// with more than one table can hash them in sequence without concatenating
+/// clones of both `Vec`s first.
+pub fn digest_hex_registers(
+    counts_groups: &[&[HashMap<String, i32>]],
+    shots: i32,
+    selected_classical_registers: &[i32],
+) -> String {
+    let mut hasher = Sha3_256::new();
+    for counts in counts_groups {
+        for data in counts.iter() {
+            let mut entries: Vec<(&String, &i32)> = data.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            for (bit_string, count) in entries {
+                hasher.update(bit_string.as_bytes());
+                hasher.update(count.to_le_bytes());
+            }
+            hasher.update(b"|");
+        }
+    }
+    hasher.update(shots.to_le_bytes());
+    for register in selected_classical_registers {
+        hasher.update(register.to_le_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn cache_file(cache_dir: &str, digest: &str) -> PathBuf {
+    Path::new(cache_dir).join(format!("{}.bincode", digest))
+}
+
+/// Look up a previously computed loader map by content digest, returning
+/// `None` on a cache miss (missing file or a corrupt/unreadable entry).
+pub fn load_cached(cache_dir: &str, digest: &str) -> Option<IdentifierMap> {
+    let raw = fs::read(cache_file(cache_dir, digest)).ok()?;
+    let entries: Vec<(i32, f64)> = bincode::deserialize(&raw).ok()?;
+    let mut loader = identifier_map_with_capacity(entries.len());
+    for (idx, value) in entries {
+        loader.insert(idx, value);
+    }
+    Some(loader)
+}
+
+/// Persist a loader map under its content digest so later sweeps over the
+/// same inputs become a lookup instead of a recompute.
+pub fn store_cached(cache_dir: &str, digest: &str, loader: &IdentifierMap) {
+    let entries: Vec<(i32, f64)> = loader.iter().map(|(idx, value)| (*idx, *value)).collect();
+    if let Ok(encoded) = bincode::serialize(&entries) {
+        let _ = fs::create_dir_all(cache_dir);
+        let _ = fs::write(cache_file(cache_dir, digest), encoded);
+    }
+}
+
+/// Python-facing counterpart of `store_cached`: serializes `loader` (the
+/// per-cell `idx -> purity/echo` results) under a digest of `(shots,
+/// selected_classical_registers, counts fingerprint)`, so a later call with
+/// the same inputs can skip recomputation via `load_cells_rust`. Returns the
+/// digest the loader was stored under; `load_cells_rust` recomputes it from
+/// `counts` rather than accepting a digest.
+#[pyfunction]
+#[pyo3(signature = (cache_dir, counts, shots, selected_classical_registers, loader))]
+pub fn dump_cells_rust(
+    cache_dir: String,
+    counts: Vec<HashMap<String, i32>>,
+    shots: i32,
+    selected_classical_registers: Vec<i32>,
+    loader: HashMap<i32, f64>,
+) -> String {
+    let digest = digest_hex_registers(&[&counts], shots, &selected_classical_registers);
+    let mut identifier_loader = identifier_map_with_capacity(loader.len());
+    for (idx, value) in loader {
+        identifier_loader.insert(idx, value);
+    }
+    store_cached(&cache_dir, &digest, &identifier_loader);
+    digest
+}
+
+/// Python-facing counterpart of `load_cached`, keyed the same way as
+/// `dump_cells_rust`. Returns `None` on a cache miss (including a stale
+/// cache produced by different `counts`/`shots`/registers).
+#[pyfunction]
+#[pyo3(signature = (cache_dir, counts, shots, selected_classical_registers))]
+pub fn load_cells_rust(
+    cache_dir: String,
+    counts: Vec<HashMap<String, i32>>,
+    shots: i32,
+    selected_classical_registers: Vec<i32>,
+) -> Option<HashMap<i32, f64>> {
+    let digest = digest_hex_registers(&[&counts], shots, &selected_classical_registers);
+    load_cached(&cache_dir, &digest).map(|loader| loader.into_iter().collect())
+}
@@ -0,0 +1,33 @@
+extern crate bincode;
+extern crate pyo3;
+extern crate serde;
+
+use bincode::Options;
+use pyo3::exceptions::PyIOError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+
+/// Deserialize the same `Vec<HashMap<String, i32>>` counts structure that
+/// `entangled_entropy_core_rust` consumes from a compact bincode file,
+/// instead of paying `serde_json::from_str` on a multi-hundred-MB JSON dump.
+#[pyfunction]
+pub fn load_counts_bincode_rust(path: &str) -> PyResult<Vec<HashMap<String, i32>>> {
+    let raw = fs::read(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+    bincode::options()
+        .with_fixint_encoding()
+        .deserialize(&raw)
+        .map_err(|err| PyIOError::new_err(err.to_string()))
+}
+
+/// Companion to `load_counts_bincode_rust`: write the counts structure out as
+/// a compact bincode file so repeated analysis runs over the same shot data
+/// can skip JSON parsing entirely.
+#[pyfunction]
+pub fn dump_counts_bincode_rust(counts: Vec<HashMap<String, i32>>, path: &str) -> PyResult<()> {
+    let encoded = bincode::options()
+        .with_fixint_encoding()
+        .serialize(&counts)
+        .map_err(|err| PyIOError::new_err(err.to_string()))?;
+    fs::write(path, encoded).map_err(|err| PyIOError::new_err(err.to_string()))
+}
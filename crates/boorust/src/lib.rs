@@ -1,19 +1,37 @@
+mod cache;
 mod hadamard;
+mod io;
+mod nohash;
 mod randomized;
+mod shadow;
+mod stream;
 mod tool;
 extern crate pyo3;
 
 use pyo3::prelude::*;
 
+use crate::cache::{dump_cells_rust, load_cells_rust};
 use crate::hadamard::purity_echo_core_rust;
+use crate::io::{dump_counts_bincode_rust, load_counts_bincode_rust};
+use crate::shadow::{shadow_expectation_rust, shadow_fidelity_rust};
+use crate::stream::entangled_entropy_core_stream_rust;
 use crate::randomized::construct::{
     cycling_slice_rust, degree_handler_rust, qubit_selector_rust, test_construct,
 };
-use crate::randomized::echo::overlap_echo_core_rust;
-use crate::randomized::entropy::entangled_entropy_core_rust;
+use crate::randomized::echo::{
+    overlap_echo_core_2_rust, overlap_echo_core_rust, overlap_echo_core_with_error_rust,
+};
+use crate::randomized::ensemble::{
+    global_index_to_basis_rust, materialize_bases_rust, reseed_stream_rust,
+};
+use crate::randomized::entropy::{
+    entangled_entropy_core_mean_rust, entangled_entropy_core_rust,
+    entangled_entropy_core_with_error_rust, purity_bootstrap_rust, purity_jackknife_rust,
+};
 use crate::randomized::entropy_2::entangled_entropy_core_2_rust;
 use crate::randomized::randomized::{
-    echo_cell_rust, ensemble_cell_rust, hamming_distance_rust, purity_cell_2_rust, purity_cell_rust,
+    echo_cell_2_rust, echo_cell_rust, ensemble_cell_rust, generate_random_bases_rust,
+    hamming_distance_rust, purity_cell_2_rust, purity_cell_rust,
 };
 use crate::tool::{make_dummy_case_32, make_two_bit_str_32, make_two_bit_str_unlimit};
 
@@ -28,17 +46,41 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     // construct
     randomized.add_function(wrap_pyfunction!(ensemble_cell_rust, &randomized)?)?;
     randomized.add_function(wrap_pyfunction!(hamming_distance_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(generate_random_bases_rust, &randomized)?)?;
     // core
     randomized.add_function(wrap_pyfunction!(purity_cell_rust, &randomized)?)?;
     randomized.add_function(wrap_pyfunction!(echo_cell_rust, &randomized)?)?;
     randomized.add_function(wrap_pyfunction!(purity_cell_2_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(echo_cell_2_rust, &randomized)?)?;
     // main
     randomized.add_function(wrap_pyfunction!(entangled_entropy_core_rust, &randomized)?)?;
     randomized.add_function(wrap_pyfunction!(
         entangled_entropy_core_2_rust,
         &randomized
     )?)?;
+    randomized.add_function(wrap_pyfunction!(
+        entangled_entropy_core_mean_rust,
+        &randomized
+    )?)?;
     randomized.add_function(wrap_pyfunction!(overlap_echo_core_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(overlap_echo_core_2_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(
+        overlap_echo_core_with_error_rust,
+        &randomized
+    )?)?;
+    randomized.add_function(wrap_pyfunction!(purity_bootstrap_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(purity_jackknife_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(
+        entangled_entropy_core_with_error_rust,
+        &randomized
+    )?)?;
+    randomized.add_function(wrap_pyfunction!(
+        entangled_entropy_core_stream_rust,
+        &randomized
+    )?)?;
+    randomized.add_function(wrap_pyfunction!(materialize_bases_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(reseed_stream_rust, &randomized)?)?;
+    randomized.add_function(wrap_pyfunction!(global_index_to_basis_rust, &randomized)?)?;
 
     let construct = PyModule::new(parent_module.py(), "construct")?;
     construct.add_function(wrap_pyfunction!(qubit_selector_rust, &construct)?)?;
@@ -48,6 +90,10 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     let hadamard = PyModule::new(parent_module.py(), "hadamard")?;
     hadamard.add_function(wrap_pyfunction!(purity_echo_core_rust, &hadamard)?)?;
 
+    let shadow = PyModule::new(parent_module.py(), "shadow")?;
+    shadow.add_function(wrap_pyfunction!(shadow_expectation_rust, &shadow)?)?;
+    shadow.add_function(wrap_pyfunction!(shadow_fidelity_rust, &shadow)?)?;
+
     let dummy = PyModule::new(parent_module.py(), "dummy")?;
     dummy.add_function(wrap_pyfunction!(make_two_bit_str_32, &dummy)?)?;
     dummy.add_function(wrap_pyfunction!(make_dummy_case_32, &dummy)?)?;
@@ -56,11 +102,22 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     let test = PyModule::new(parent_module.py(), "test")?;
     test.add_function(wrap_pyfunction!(test_construct, &test)?)?;
 
+    let io = PyModule::new(parent_module.py(), "io")?;
+    io.add_function(wrap_pyfunction!(load_counts_bincode_rust, &io)?)?;
+    io.add_function(wrap_pyfunction!(dump_counts_bincode_rust, &io)?)?;
+
+    let cache = PyModule::new(parent_module.py(), "cache")?;
+    cache.add_function(wrap_pyfunction!(dump_cells_rust, &cache)?)?;
+    cache.add_function(wrap_pyfunction!(load_cells_rust, &cache)?)?;
+
     parent_module.add_submodule(&randomized)?;
     parent_module.add_submodule(&construct)?;
     parent_module.add_submodule(&hadamard)?;
     parent_module.add_submodule(&dummy)?;
     parent_module.add_submodule(&test)?;
+    parent_module.add_submodule(&io)?;
+    parent_module.add_submodule(&shadow)?;
+    parent_module.add_submodule(&cache)?;
     Ok(())
 }
 
@@ -0,0 +1,23 @@
+extern crate nohash_hasher;
+
+use nohash_hasher::NoHashHasher;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+/// `HashMap<i32, f64>` keyed by a dense, already-unique identifier (the
+/// `.enumerate()` index used by every `*_core_rust` entry point). The
+/// identifier is used directly as the hash instead of paying SipHash on a
+/// key that is already `0..N`.
+pub type IdentifierMap = HashMap<i32, f64, BuildHasherDefault<NoHashHasher<i32>>>;
+
+pub fn identifier_map_with_capacity(capacity: usize) -> IdentifierMap {
+    IdentifierMap::with_capacity_and_hasher(capacity, BuildHasherDefault::default())
+}
+
+/// Sum a rayon-produced `(identifier, value)` stream straight into a mean,
+/// without materializing the intermediate loader map, for callers that only
+/// need the average over all cells.
+pub fn mean_of_pairs(pairs: &[(i32, f64)]) -> f64 {
+    let sum: f64 = pairs.iter().map(|(_, value)| value).sum();
+    sum / (pairs.len() as f64)
+}
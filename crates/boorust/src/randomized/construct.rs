@@ -11,6 +11,57 @@ pub enum QubitDegree {
     Single(i32),
 }
 
+/// Pack a measured computational-basis bitstring (a string of `'0'`/`'1'`,
+/// up to 128 characters, leftmost char as the most significant bit) into a
+/// `u128` once, so subsystem selection becomes a mask-and-gather on an
+/// integer instead of repeated `O(L)` character slicing. Errors instead of
+/// silently dropping the leading bits when `bit_string` is longer than the
+/// 128-bit representation can hold.
+pub fn pack_bitstring_rust(bit_string: &str) -> PyResult<u128> {
+    let len = bit_string.len();
+    if len > 128 {
+        return Err(PyValueError::new_err(format!(
+            "bit_string of length {} exceeds the 128-bit packed representation.",
+            len
+        )));
+    }
+    let mut packed: u128 = 0;
+    for c in bit_string.chars() {
+        packed = (packed << 1) | (c == '1') as u128;
+    }
+    Ok(packed)
+}
+
+/// Gather the classical-register bits at `indices` directly out of the raw
+/// `'0'/'1'` string into a packed integer, without first packing the whole
+/// string into a fixed-width integer. This is what lets
+/// `selected_classical_registers`-style subsystem selection keep working on
+/// circuits with more than 128 classical registers, as long as the
+/// *selected* subsystem itself is at most 128 bits wide — packing the whole
+/// string first with `pack_bitstring_rust` would reject those circuits even
+/// when only a small subsystem is requested.
+pub fn gather_bits_from_str(bit_string: &str, indices: &[i32]) -> PyResult<u128> {
+    if indices.len() > 128 {
+        return Err(PyValueError::new_err(format!(
+            "selected subsystem of {} classical registers exceeds the 128-bit packed representation.",
+            indices.len()
+        )));
+    }
+    let bytes = bit_string.as_bytes();
+    let mut result: u128 = 0;
+    for &i in indices {
+        let bit = (bytes[i as usize] == b'1') as u128;
+        result = (result << 1) | bit;
+    }
+    Ok(result)
+}
+
+/// Hamming distance between two packed bitstrings: `XOR` then
+/// `count_ones()`, replacing the per-character string comparison.
+pub fn hamming_distance_packed_rust(a: u128, b: u128) -> u32 {
+    (a ^ b).count_ones()
+}
+
 #[pyfunction]
 pub fn cycling_slice_rust(target: &str, start: i32, end: i32, step: i32) -> PyResult<String> {
     let length = target.len() as i32;
@@ -2,22 +2,31 @@ extern crate pyo3;
 extern crate rayon;
 
 use pyo3::prelude::*;
-use rayon::iter::IntoParallelRefIterator;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::time::Instant;
 
+use crate::cache::{digest_hex, digest_hex_registers, load_cached, store_cached};
+use crate::nohash::identifier_map_with_capacity;
 use crate::randomized::construct::{degree_handler_rust, QubitDegree};
-use crate::randomized::randomized::echo_cell_rust;
+use crate::randomized::entropy::{purity_bootstrap_rust, purity_jackknife_rust};
+use crate::randomized::randomized::{echo_cell_2_impl, echo_cell_impl};
 
 #[pyfunction]
-#[pyo3(signature = (shots, counts, degree=None, measure=None))]
+#[pyo3(signature = (shots, counts, degree=None, measure=None, cache_dir=None))]
 pub fn overlap_echo_core_rust(
     shots: i32,
     counts: Vec<HashMap<String, i32>>,
     degree: Option<QubitDegree>,
     measure: Option<(i32, i32)>,
-) -> (HashMap<i32, f64>, (i32, i32), (i32, i32), &'static str, f64) {
+    cache_dir: Option<String>,
+) -> (
+    crate::nohash::IdentifierMap,
+    (i32, i32),
+    (i32, i32),
+    &'static str,
+    f64,
+) {
     // check if the sum of shots is equal to the sum of all counts
     let sample_shots: i32 = counts[0].values().sum();
     assert!(shots == sample_shots);
@@ -36,31 +45,30 @@ pub fn overlap_echo_core_rust(
     );
     let times = counts.len() / 2;
 
-    let counts_pair: Vec<(HashMap<String, i32>, HashMap<String, i32>)> = (0..times)
-        .map(|i| {
-            let first_counts = counts[i].clone();
-            let second_counts = counts[i + times].clone();
-            (first_counts, second_counts)
-        })
-        .collect();
-
     let begin: Instant = Instant::now();
 
-    let mut echo_loader_2: HashMap<i32, f64> = HashMap::new();
-    let result_vec = counts_pair
-        .par_iter()
-        .enumerate()
-        .map(|(identifier, (data, data2))| {
-            let result: (i32, f64) = echo_cell_rust(
-                identifier as i32,
-                data.clone(),
-                data2.clone(),
-                bitstring_range,
-                subsystems_size,
-            );
-            // println!("| purity_cell: {:?} {}", result, subsystems_size);
-            result
-        });
+    let digest = cache_dir
+        .as_ref()
+        .map(|_| digest_hex(&counts, bitstring_range, actual_measure, subsystems_size));
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        if let Some(cached) = load_cached(dir, digest) {
+            let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
+            return (cached, bitstring_range, actual_measure, "", duration_2);
+        }
+    }
+
+    let mut echo_loader_2 = identifier_map_with_capacity(times);
+    let result_vec = (0..times).into_par_iter().map(|identifier| {
+        let result: (i32, f64) = echo_cell_impl(
+            identifier as i32,
+            &counts[identifier],
+            &counts[identifier + times],
+            bitstring_range,
+            subsystems_size,
+        );
+        // println!("| purity_cell: {:?} {}", result, subsystems_size);
+        result
+    });
     result_vec
         .collect::<Vec<(i32, f64)>>()
         .iter()
@@ -68,6 +76,10 @@ pub fn overlap_echo_core_rust(
             echo_loader_2.insert(*idx, *purity_cell);
         });
 
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        store_cached(dir, digest, &echo_loader_2);
+    }
+
     let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
 
     (
@@ -78,3 +90,150 @@ pub fn overlap_echo_core_rust(
         duration_2,
     )
 }
+
+/// `overlap_echo_core_rust` plus a statistical error on the averaged echo:
+/// a seeded bootstrap `(mean_of_means, std, (p2_5, p97_5))` and a jackknife
+/// `(mean, std)`, reusing the same error routines as
+/// `entangled_entropy_core_with_error_rust`.
+#[pyfunction]
+#[pyo3(signature = (shots, counts, degree=None, measure=None, n_resamples=1000, seed=0))]
+pub fn overlap_echo_core_with_error_rust(
+    shots: i32,
+    counts: Vec<HashMap<String, i32>>,
+    degree: Option<QubitDegree>,
+    measure: Option<(i32, i32)>,
+    n_resamples: usize,
+    seed: u64,
+) -> PyResult<(
+    crate::nohash::IdentifierMap,
+    (i32, i32),
+    (i32, i32),
+    f64,
+    f64, f64, (f64, f64),
+    f64, f64,
+)> {
+    let (echo_loader, bitstring_range, actual_measure, _, duration) =
+        overlap_echo_core_rust(shots, counts, degree, measure, None);
+    let (bootstrap_mean, bootstrap_std, bootstrap_ci) =
+        purity_bootstrap_rust(echo_loader.clone(), n_resamples, seed);
+    let (jackknife_mean, jackknife_std) = purity_jackknife_rust(echo_loader.clone())?;
+
+    Ok((
+        echo_loader,
+        bitstring_range,
+        actual_measure,
+        duration,
+        bootstrap_mean,
+        bootstrap_std,
+        bootstrap_ci,
+        jackknife_mean,
+        jackknife_std,
+    ))
+}
+
+/// Register-selected counterpart of `overlap_echo_core_rust`: `first_counts`
+/// and `second_counts` are two independent count tables (rather than one
+/// table split in half), and the subsystem is picked by
+/// `selected_classical_registers` the same way `entangled_entropy_core_2_rust`
+/// picks it. `cache_dir`, when set, short-circuits the rayon reduction on a
+/// digest hit keyed on `(shots, selected_classical_registers, counts
+/// fingerprint)`.
+#[pyfunction]
+#[pyo3(signature = (shots, first_counts, second_counts, selected_classical_registers=None, cache_dir=None))]
+pub fn overlap_echo_core_2_rust(
+    shots: i32,
+    first_counts: Vec<HashMap<String, i32>>,
+    second_counts: Vec<HashMap<String, i32>>,
+    selected_classical_registers: Option<Vec<i32>>,
+    cache_dir: Option<String>,
+) -> (crate::nohash::IdentifierMap, Vec<i32>, &'static str, f64) {
+    assert_eq!(
+        first_counts.len(),
+        second_counts.len(),
+        "The number of counts must be equal, but the first count is {}, and the second count is {}",
+        first_counts.len(),
+        second_counts.len(),
+    );
+
+    let sample_shots_01: i32 = first_counts[0].values().sum();
+    let sample_shots_02: i32 = second_counts[0].values().sum();
+    assert_eq!(shots, sample_shots_01, "shots does not match first_counts");
+    assert_eq!(shots, sample_shots_02, "shots does not match second_counts");
+
+    let measured_system_size: i32 = first_counts[0].keys().next().unwrap().len() as i32;
+    let selected_classical_registers_actual = match selected_classical_registers {
+        Some(selected_classical_registers) => selected_classical_registers,
+        None => (0..measured_system_size).collect(),
+    };
+
+    let begin: Instant = Instant::now();
+
+    let digest = cache_dir.as_ref().map(|_| {
+        digest_hex_registers(
+            &[&first_counts, &second_counts],
+            shots,
+            &selected_classical_registers_actual,
+        )
+    });
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        if let Some(cached) = load_cached(dir, digest) {
+            let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
+            let mut selected_sorted = selected_classical_registers_actual;
+            selected_sorted.sort();
+            return (cached, selected_sorted, "", duration_2);
+        }
+    }
+
+    let mut echo_loader_2 = identifier_map_with_capacity(first_counts.len());
+    let result_vec = (0..first_counts.len()).into_par_iter().map(|identifier| {
+        echo_cell_2_impl(
+            identifier as i32,
+            &first_counts[identifier],
+            &second_counts[identifier],
+            selected_classical_registers_actual.clone(),
+        )
+    });
+
+    let selected_classical_registers_actual_sorted = {
+        let mut selected_sorted_inner = selected_classical_registers_actual.clone();
+        selected_sorted_inner.sort();
+        selected_sorted_inner
+    };
+    let mut selected_classical_registers_checked: HashMap<i32, Vec<i32>> = HashMap::new();
+    result_vec
+        .collect::<Vec<(i32, f64, Vec<i32>)>>()
+        .iter()
+        .for_each(
+            |(idx, echo_cell, selected_classical_registers_sorted_result)| {
+                echo_loader_2.insert(*idx, *echo_cell);
+
+                let compare = selected_classical_registers_actual_sorted
+                    .iter()
+                    .zip(selected_classical_registers_sorted_result.iter())
+                    .all(|(a, b)| a == b);
+                if !compare {
+                    selected_classical_registers_checked
+                        .insert(*idx, selected_classical_registers_sorted_result.clone());
+                }
+            },
+        );
+    if selected_classical_registers_checked.len() > 0 {
+        println!(
+            "Selected classical registers are not the same: {:?}",
+            selected_classical_registers_checked
+        );
+    }
+
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        store_cached(dir, digest, &echo_loader_2);
+    }
+
+    let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
+
+    (
+        echo_loader_2,
+        selected_classical_registers_actual_sorted,
+        "",
+        duration_2,
+    )
+}
@@ -0,0 +1,54 @@
+extern crate pyo3;
+extern crate rand_chacha;
+extern crate rand_core;
+
+use pyo3::prelude::*;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use crate::randomized::randomized::sample_basis;
+
+/// Materialize the full `(num_unitaries, num_qubits)` basis array for a
+/// single integer `seed`, using a counter-based `ChaCha20Rng` so the exact
+/// same seed reproduces byte-identical bases on any platform. Draws via
+/// `sample_basis` (the same function `generate_random_bases_rust` uses),
+/// so the two entry points agree on a basis sequence for a given seed
+/// instead of silently diverging.
+#[pyfunction]
+#[pyo3(signature = (num_qubits, num_unitaries, seed))]
+pub fn materialize_bases_rust(num_qubits: i32, num_unitaries: i32, seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    (0..num_unitaries)
+        .map(|_| (0..num_qubits).map(|_| sample_basis(&mut rng, &None)).collect())
+        .collect()
+}
+
+/// Derive a per-experiment seed from a shared base `seed` and an
+/// `experiment_offset`, so multiple experiments sharing one base seed draw
+/// from independent-but-reproducible ChaCha20 streams (one counter offset
+/// per experiment) instead of colliding on the same sequence.
+#[pyfunction]
+#[pyo3(signature = (seed, experiment_offset))]
+pub fn reseed_stream_rust(seed: u64, experiment_offset: u64) -> u64 {
+    seed.wrapping_mul(0x9E3779B97F4A7C15).wrapping_add(experiment_offset)
+}
+
+/// Map a flat global index (`unitary * num_qubits + qubit`) to its
+/// `(unitary, qubit)` coordinates and the basis value at that position,
+/// without materializing any earlier draw: the ChaCha20 stream is seeked
+/// directly to the index's word via `set_word_pos`, relying on
+/// `sample_basis`'s unweighted draw consuming exactly one word so the seek
+/// lands on the same basis `materialize_bases_rust` would have produced at
+/// that position.
+#[pyfunction]
+#[pyo3(signature = (num_qubits, seed, global_index))]
+pub fn global_index_to_basis_rust(num_qubits: i32, seed: u64, global_index: u64) -> (i32, i32, u8) {
+    let unitary = (global_index / (num_qubits as u64)) as i32;
+    let qubit = (global_index % (num_qubits as u64)) as i32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    rng.set_word_pos(global_index as u128);
+    let basis = sample_basis(&mut rng, &None);
+
+    (unitary, qubit, basis)
+}
@@ -1,23 +1,38 @@
 extern crate pyo3;
+extern crate rand;
+extern crate rand_chacha;
 extern crate rayon;
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::time::Instant;
 
+use crate::cache::{digest_hex, load_cached, store_cached};
+use crate::nohash::identifier_map_with_capacity;
 use crate::randomized::construct::{degree_handler_rust, QubitDegree};
-use crate::randomized::randomized::purity_cell_rust;
+use crate::randomized::randomized::purity_cell_impl;
 
 #[pyfunction]
-#[pyo3(signature = (shots, counts, degree=None, measure=None))]
+#[pyo3(signature = (shots, counts, degree=None, measure=None, cache_dir=None))]
 pub fn entangled_entropy_core_rust(
     shots: i32,
     counts: Vec<HashMap<String, i32>>,
     degree: Option<QubitDegree>,
     measure: Option<(i32, i32)>,
-) -> (HashMap<i32, f64>, (i32, i32), (i32, i32), &'static str, f64) {
+    cache_dir: Option<String>,
+) -> (
+    crate::nohash::IdentifierMap,
+    (i32, i32),
+    (i32, i32),
+    &'static str,
+    f64,
+) {
     // check if the sum of shots is equal to the sum of all counts
     let sample_shots: i32 = counts[0].values().sum();
     assert!(shots == sample_shots);
@@ -31,14 +46,20 @@ pub fn entangled_entropy_core_rust(
 
     let begin: Instant = Instant::now();
 
-    let mut purity_loader_2: HashMap<i32, f64> = HashMap::new();
+    let digest = cache_dir
+        .as_ref()
+        .map(|_| digest_hex(&counts, bitstring_range, actual_measure, subsystems_size));
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        if let Some(cached) = load_cached(dir, digest) {
+            let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
+            return (cached, bitstring_range, actual_measure, "", duration_2);
+        }
+    }
+
+    let mut purity_loader_2 = identifier_map_with_capacity(counts.len());
     let result_vec = counts.par_iter().enumerate().map(|(identifier, data)| {
-        let result: (i32, f64) = purity_cell_rust(
-            identifier as i32,
-            data.clone(),
-            bitstring_range,
-            subsystems_size,
-        );
+        let result: (i32, f64) =
+            purity_cell_impl(identifier as i32, data, bitstring_range, subsystems_size);
         // println!("| purity_cell: {:?} {}", result, subsystems_size);
         result
     });
@@ -49,6 +70,10 @@ pub fn entangled_entropy_core_rust(
             purity_loader_2.insert(*idx, *purity_cell);
         });
 
+    if let (Some(dir), Some(digest)) = (cache_dir.as_ref(), digest.as_ref()) {
+        store_cached(dir, digest, &purity_loader_2);
+    }
+
     let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
 
     (
@@ -59,3 +84,156 @@ pub fn entangled_entropy_core_rust(
         duration_2,
     )
 }
+
+/// Same computation as `entangled_entropy_core_rust`, but for callers that
+/// only need the mean purity: sums the rayon results directly instead of
+/// materializing the per-cell loader map first.
+#[pyfunction]
+#[pyo3(signature = (shots, counts, degree=None, measure=None))]
+pub fn entangled_entropy_core_mean_rust(
+    shots: i32,
+    counts: Vec<HashMap<String, i32>>,
+    degree: Option<QubitDegree>,
+    measure: Option<(i32, i32)>,
+) -> (f64, (i32, i32), (i32, i32), f64) {
+    let sample_shots: i32 = counts[0].values().sum();
+    assert!(shots == sample_shots);
+
+    let allsystems_size: i32 = counts[0].keys().next().unwrap().len() as i32;
+    let (bitstring_range, actual_measure, subsystems_size) =
+        degree_handler_rust(allsystems_size, degree, measure);
+
+    let begin: Instant = Instant::now();
+
+    let cells: Vec<(i32, f64)> = counts
+        .par_iter()
+        .enumerate()
+        .map(|(identifier, data)| {
+            purity_cell_impl(identifier as i32, data, bitstring_range, subsystems_size)
+        })
+        .collect();
+    let mean = crate::nohash::mean_of_pairs(&cells);
+
+    let duration_2: f64 = begin.elapsed().as_secs_f64() as f64;
+
+    (mean, bitstring_range, actual_measure, duration_2)
+}
+
+/// Nonparametric bootstrap over the per-unitary purity cells produced by
+/// `entangled_entropy_core_rust`.
+///
+/// Draws `n_resamples` index-sets of size `N` (`N` = number of cells) with
+/// replacement from `0..N` using a `ChaCha20Rng` seeded from `seed`, computes
+/// the mean purity for each resample, and returns `(mean_of_means, std,
+/// (p2_5, p97_5))` over the resample means. This gives a statistically
+/// principled confidence interval on the 2nd-Rényi entropy without rerunning
+/// the experiment, and the fixed seed keeps it reproducible.
+#[pyfunction]
+#[pyo3(signature = (purity_cells, n_resamples, seed))]
+pub fn purity_bootstrap_rust(
+    purity_cells: crate::nohash::IdentifierMap,
+    n_resamples: usize,
+    seed: u64,
+) -> (f64, f64, (f64, f64)) {
+    let cells: Vec<f64> = purity_cells.values().copied().collect();
+    let n = cells.len();
+
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut resample_means: Vec<f64> = (0..n_resamples)
+        .map(|_| {
+            let sum: f64 = (0..n).map(|_| cells[rng.gen_range(0..n)]).sum();
+            sum / (n as f64)
+        })
+        .collect();
+    resample_means.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mean_of_means = resample_means.iter().sum::<f64>() / (n_resamples as f64);
+    let variance = resample_means
+        .iter()
+        .map(|m| (m - mean_of_means).powi(2))
+        .sum::<f64>()
+        / (n_resamples as f64);
+    let std = variance.sqrt();
+
+    let lower_idx = ((0.025 * (n_resamples as f64)) as usize).min(n_resamples - 1);
+    let upper_idx = ((0.975 * (n_resamples as f64)) as usize).min(n_resamples - 1);
+    let percentile_interval = (resample_means[lower_idx], resample_means[upper_idx]);
+
+    (mean_of_means, std, percentile_interval)
+}
+
+/// Leave-one-out jackknife error over a set of per-cell purity/echo values.
+///
+/// For each cell `i`, `m_i` is the mean over all cells but `i`; the jackknife
+/// variance is `((N-1)/N) * sum((m_i - mean(m))^2)`. Returns `(mean, std)`
+/// where `std` is the square root of that variance. This is a cheaper,
+/// deterministic alternative to `purity_bootstrap_rust` when a resampling
+/// seed is not wanted. Requires at least 2 cells, since a single cell's
+/// leave-one-out mean divides by `n - 1 == 0`.
+#[pyfunction]
+#[pyo3(signature = (purity_cells))]
+pub fn purity_jackknife_rust(purity_cells: crate::nohash::IdentifierMap) -> PyResult<(f64, f64)> {
+    let cells: Vec<f64> = purity_cells.values().copied().collect();
+    let n = cells.len();
+    if n < 2 {
+        return Err(PyValueError::new_err(format!(
+            "purity_jackknife_rust needs at least 2 cells, got {}",
+            n
+        )));
+    }
+    let total: f64 = cells.iter().sum();
+    let mean = total / (n as f64);
+
+    let leave_one_out_means: Vec<f64> = cells
+        .iter()
+        .map(|value| (total - value) / ((n - 1) as f64))
+        .collect();
+    let loo_mean = leave_one_out_means.iter().sum::<f64>() / (n as f64);
+    let variance = ((n - 1) as f64 / n as f64)
+        * leave_one_out_means
+            .iter()
+            .map(|m| (m - loo_mean).powi(2))
+            .sum::<f64>();
+
+    Ok((mean, variance.sqrt()))
+}
+
+/// `entangled_entropy_core_rust` plus a statistical error on the averaged
+/// purity: a seeded bootstrap `(mean_of_means, std, (p2_5, p97_5))` and a
+/// jackknife `(mean, std)`, computed from the same per-cell loader map the
+/// parallel reduction already produces.
+#[pyfunction]
+#[pyo3(signature = (shots, counts, degree=None, measure=None, n_resamples=1000, seed=0))]
+pub fn entangled_entropy_core_with_error_rust(
+    shots: i32,
+    counts: Vec<HashMap<String, i32>>,
+    degree: Option<QubitDegree>,
+    measure: Option<(i32, i32)>,
+    n_resamples: usize,
+    seed: u64,
+) -> PyResult<(
+    crate::nohash::IdentifierMap,
+    (i32, i32),
+    (i32, i32),
+    f64,
+    f64, f64, (f64, f64),
+    f64, f64,
+)> {
+    let (purity_loader, bitstring_range, actual_measure, _, duration) =
+        entangled_entropy_core_rust(shots, counts, degree, measure, None);
+    let (bootstrap_mean, bootstrap_std, bootstrap_ci) =
+        purity_bootstrap_rust(purity_loader.clone(), n_resamples, seed);
+    let (jackknife_mean, jackknife_std) = purity_jackknife_rust(purity_loader.clone())?;
+
+    Ok((
+        purity_loader,
+        bitstring_range,
+        actual_measure,
+        duration,
+        bootstrap_mean,
+        bootstrap_std,
+        bootstrap_ci,
+        jackknife_mean,
+        jackknife_std,
+    ))
+}
@@ -1,27 +1,63 @@
+extern crate blake3;
+extern crate dashmap;
 extern crate pyo3;
 extern crate rayon;
 
+use dashmap::DashMap;
 use pyo3::prelude::*;
-use rayon::iter::IntoParallelRefIterator;
-use rayon::{prelude::*, result};
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
-use crate::randomized::construct::{degree_handler_rust, QubitDegree};
-use crate::randomized::randomized::purity_cell_2_rust;
+use crate::nohash::identifier_map_with_capacity;
+use crate::randomized::randomized::purity_cell_2_impl;
 
+/// Canonical BLAKE3 digest of one `purity_cell_2_impl` input: the
+/// `(bitstring, count)` pairs sorted by bitstring, followed by the selected
+/// register list, so identical inputs hash identically regardless of the
+/// `HashMap`'s iteration order.
+fn cell_digest(single_counts: &HashMap<String, i32>, selected_classical_registers: &[i32]) -> [u8; 32] {
+    let mut entries: Vec<(&String, &i32)> = single_counts.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let mut hasher = blake3::Hasher::new();
+    for (bit_string, count) in entries {
+        hasher.update(bit_string.as_bytes());
+        hasher.update(&count.to_le_bytes());
+    }
+    for register in selected_classical_registers {
+        hasher.update(&register.to_le_bytes());
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// Same purity core as `entangled_entropy_core_rust`, but subsystem-selected
+/// via explicit registers instead of a `(range, measure)` pair. When
+/// `enable_cache` is set, repeated `single_counts`/register combinations
+/// within this call are memoized in a `DashMap<[u8;32], f64>` keyed on
+/// `cell_digest`, skipping the `O(n^2)` ensemble double loop on a hit;
+/// `cache_hits`/`cache_misses` report how much that saved.
 #[pyfunction]
+#[pyo3(signature = (shots, counts, selected_classical_registers=None, enable_cache=false))]
 pub fn entangled_entropy_core_2_rust(
     shots: i32,
     counts: Vec<HashMap<String, i32>>,
     selected_classical_registers: Option<Vec<i32>>,
-) -> (HashMap<i32, f64>, Vec<i32>, &'static str, f64) {
+    enable_cache: bool,
+) -> (
+    crate::nohash::IdentifierMap,
+    Vec<i32>,
+    &'static str,
+    f64,
+    u64,
+    u64,
+) {
     // check if the sum of shots is equal to the sum of all counts
     let sample_shots: i32 = counts[0].values().sum();
     assert_eq!(
         shots, sample_shots,
         "shots {} does not match sample_shots {}",
-        a, b
+        shots, sample_shots
     );
 
     // Determine the size of the allsystems
@@ -34,13 +70,34 @@ pub fn entangled_entropy_core_2_rust(
 
     let begin: Instant = Instant::now();
 
+    let cell_cache: DashMap<[u8; 32], f64> = DashMap::new();
+    let cache_hits = AtomicU64::new(0);
+    let cache_misses = AtomicU64::new(0);
+
     let result_vec = counts.par_iter().enumerate().map(|(identifier, data)| {
-        let result: (i32, f64, Vec<i32>) = purity_cell_2_rust(
+        if !enable_cache {
+            return purity_cell_2_impl(
+                identifier as i32,
+                data,
+                selected_classical_registers_actual.clone(),
+            );
+        }
+
+        let digest = cell_digest(data, &selected_classical_registers_actual);
+        if let Some(cached_purity) = cell_cache.get(&digest) {
+            cache_hits.fetch_add(1, Ordering::Relaxed);
+            let mut selected_sorted = selected_classical_registers_actual.clone();
+            selected_sorted.sort();
+            return (identifier as i32, *cached_purity, selected_sorted);
+        }
+
+        let result: (i32, f64, Vec<i32>) = purity_cell_2_impl(
             identifier as i32,
-            data.clone(),
+            data,
             selected_classical_registers_actual.clone(),
         );
-        // println!("| purity_cell: {:?} {}", result, subsystems_size);
+        cache_misses.fetch_add(1, Ordering::Relaxed);
+        cell_cache.insert(digest, result.1);
         result
     });
 
@@ -49,7 +106,7 @@ pub fn entangled_entropy_core_2_rust(
         selected_sorted_inner.sort();
         selected_sorted_inner
     };
-    let mut purity_loader_2: HashMap<i32, f64> = HashMap::new();
+    let mut purity_loader_2 = identifier_map_with_capacity(counts.len());
     let mut selected_classical_registers_checked: HashMap<i32, Vec<i32>> = HashMap::new();
     result_vec
         .collect::<Vec<(i32, f64, Vec<i32>)>>()
@@ -82,5 +139,7 @@ pub fn entangled_entropy_core_2_rust(
         selected_classical_registers_actual_sorted,
         "",
         duration_2,
+        cache_hits.load(Ordering::Relaxed),
+        cache_misses.load(Ordering::Relaxed),
     )
 }
@@ -0,0 +1,6 @@
+pub mod construct;
+pub mod echo;
+pub mod ensemble;
+pub mod entropy;
+pub mod entropy_2;
+pub mod randomized;
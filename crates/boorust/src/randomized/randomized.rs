@@ -1,6 +1,70 @@
+extern crate ahash;
 extern crate pyo3;
+extern crate rand;
+extern crate rand_chacha;
+extern crate rayon;
 
+use ahash::AHashMap;
 use pyo3::prelude::*;
+use rand::Rng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::randomized::construct::{
+    cycling_slice_rust, gather_bits_from_str, hamming_distance_packed_rust, pack_bitstring_rust,
+};
+
+/// Draw one measurement-basis index (0 = X, 1 = Y, 2 = Z) from `rng`.
+///
+/// `prob` is an optional `[p_x, p_y, p_z]` weighting; when absent each basis
+/// is equally likely, matching the unbiased Haar-2-design Clifford sampling
+/// used by classical-shadow protocols. The unweighted draw consumes exactly
+/// one `next_u32()` word (rather than `gen_range`'s variable, sometimes
+/// rejection-sampled consumption), so `randomized::ensemble`'s word-position
+/// seeking (`global_index_to_basis_rust`) can treat one draw as one word and
+/// stay in lockstep with this function.
+pub(crate) fn sample_basis(rng: &mut ChaCha20Rng, prob: &Option<[f64; 3]>) -> u8 {
+    match prob {
+        Some(weights) => {
+            let total: f64 = weights.iter().sum();
+            let draw = rng.gen::<f64>() * total;
+            if draw < weights[0] {
+                0
+            } else if draw < weights[0] + weights[1] {
+                1
+            } else {
+                2
+            }
+        }
+        None => (rng.next_u32() % 3) as u8,
+    }
+}
+
+/// Deterministically generate the per-qubit measurement bases for a
+/// randomized-measurement / classical-shadow experiment.
+///
+/// For each of `num_unitaries` circuits, draws a `num_qubits`-long sequence
+/// of basis indices (0/1/2 mapping to the X/Y/Z eigenbasis) from a
+/// `ChaCha20Rng` seeded from `seed`. Because ChaCha20 is portable, the same
+/// seed yields the same bases in Rust and in a Python reference, so results
+/// downstream (e.g. `entangled_entropy_core_rust`) stay reproducible and
+/// auditable. `prob` optionally biases the X/Y/Z draw for biased shadows.
+#[pyfunction]
+#[pyo3(signature = (num_qubits, num_unitaries, seed, prob=None))]
+pub fn generate_random_bases_rust(
+    num_qubits: i32,
+    num_unitaries: i32,
+    seed: u64,
+    prob: Option<[f64; 3]>,
+) -> Vec<Vec<u8>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    (0..num_unitaries)
+        .map(|_| (0..num_qubits).map(|_| sample_basis(&mut rng, &prob)).collect())
+        .collect()
+}
 
 #[pyfunction]
 #[pyo3(signature = (s_i, s_j))]
@@ -32,3 +96,240 @@ pub fn ensemble_cell_rust(
         * (((s_j_meas as f64) / (shots as f64)) as f64);
     tmp
 }
+
+/// Same weight as `ensemble_cell_rust`, but for packed bitstring keys: the
+/// Hamming distance is `(s_i ^ s_j).count_ones()` instead of a per-character
+/// string comparison.
+pub fn ensemble_cell_packed_rust(s_i: u128, s_i_meas: i32, s_j: u128, s_j_meas: i32, a_num: i32, shots: i32) -> f64 {
+    let diff: i32 = hamming_distance_packed_rust(s_i, s_j) as i32;
+    f64::powi(2.0, a_num)
+        * f64::powi(-2.0, -diff)
+        * ((s_i_meas as f64) / (shots as f64))
+        * ((s_j_meas as f64) / (shots as f64))
+}
+
+/// Pack `single_counts` into the subsystem selected by `bit_string_range`
+/// (or the cycling slice beyond `subsystem_size`), grouping repeats in an
+/// `ahash`-backed map keyed directly on the packed integer, then sum the
+/// pairwise ensemble weight over that group. This replaces the `O(L)`
+/// `.chars().nth(...)` / string-keyed grouping used by the naive
+/// implementation with a pack-once, mask-and-gather pipeline.
+#[pyfunction]
+#[pyo3(signature = (idx, single_counts, bit_string_range, subsystem_size))]
+pub fn purity_cell_rust(
+    idx: i32,
+    single_counts: HashMap<String, i32>,
+    bit_string_range: (i32, i32),
+    subsystem_size: i32,
+) -> (i32, f64) {
+    purity_cell_impl(idx, &single_counts, bit_string_range, subsystem_size)
+}
+
+/// Borrowing counterpart of `purity_cell_rust`, used by the `*_core_rust`
+/// dispatchers so each cell borrows into the shared input `counts` vector
+/// instead of cloning its `HashMap` before handing it to a worker.
+pub fn purity_cell_impl(
+    idx: i32,
+    single_counts: &HashMap<String, i32>,
+    bit_string_range: (i32, i32),
+    subsystem_size: i32,
+) -> (i32, f64) {
+    let shots: i32 = single_counts.values().sum();
+    let mut packed_under_degree: AHashMap<u128, i32> = AHashMap::with_capacity(single_counts.len());
+
+    if 0 <= bit_string_range.0 && bit_string_range.1 <= subsystem_size {
+        for (bit_string, count) in single_counts {
+            let substring = &bit_string[bit_string_range.0 as usize..bit_string_range.1 as usize];
+            let packed = pack_bitstring_rust(substring).unwrap_or_else(|err| panic!("Error: {}", err));
+            *packed_under_degree.entry(packed).or_insert(0) += count;
+        }
+    } else {
+        for (bit_string, count) in single_counts {
+            let substring =
+                cycling_slice_rust(bit_string, bit_string_range.0, bit_string_range.1, 1)
+                    .unwrap_or_else(|err| panic!("Error: {}", err));
+            let packed = pack_bitstring_rust(&substring).unwrap_or_else(|err| panic!("Error: {}", err));
+            *packed_under_degree.entry(packed).or_insert(0) += count;
+        }
+    }
+
+    let purity_cell: f64 = packed_under_degree
+        .par_iter()
+        .flat_map(|(s_ai, s_ai_meas)| {
+            packed_under_degree.par_iter().map(move |(s_aj, s_aj_meas)| {
+                ensemble_cell_packed_rust(*s_ai, *s_ai_meas, *s_aj, *s_aj_meas, subsystem_size, shots)
+            })
+        })
+        .sum();
+
+    (idx, purity_cell)
+}
+
+/// Two-register variant of `purity_cell_rust`: gathers the bits at
+/// `selected_classical_registers` via `gather_bits_from_str` instead of
+/// slicing, avoiding the per-qubit `.chars().nth(...)` scan entirely.
+#[pyfunction]
+#[pyo3(signature = (idx, single_counts, selected_classical_registers))]
+pub fn purity_cell_2_rust(
+    idx: i32,
+    single_counts: HashMap<String, i32>,
+    selected_classical_registers: Vec<i32>,
+) -> (i32, f64, Vec<i32>) {
+    purity_cell_2_impl(idx, &single_counts, selected_classical_registers)
+}
+
+/// Borrowing counterpart of `purity_cell_2_rust`.
+pub fn purity_cell_2_impl(
+    idx: i32,
+    single_counts: &HashMap<String, i32>,
+    selected_classical_registers: Vec<i32>,
+) -> (i32, f64, Vec<i32>) {
+    let shots: i32 = single_counts.values().sum();
+
+    let mut selected_classical_registers_sorted = selected_classical_registers.clone();
+    selected_classical_registers_sorted.sort();
+    let subsystem_size = selected_classical_registers_sorted.len() as i32;
+
+    let mut packed_under_degree: AHashMap<u128, i32> = AHashMap::with_capacity(single_counts.len());
+    for (bit_string_all, count) in single_counts {
+        let packed = gather_bits_from_str(bit_string_all, &selected_classical_registers)
+            .unwrap_or_else(|err| panic!("Error: {}", err));
+        *packed_under_degree.entry(packed).or_insert(0) += count;
+    }
+
+    let purity_cell: f64 = packed_under_degree
+        .par_iter()
+        .flat_map(|(s_ai, s_ai_meas)| {
+            packed_under_degree.par_iter().map(move |(s_aj, s_aj_meas)| {
+                ensemble_cell_packed_rust(*s_ai, *s_ai_meas, *s_aj, *s_aj_meas, subsystem_size, shots)
+            })
+        })
+        .sum();
+
+    (idx, purity_cell, selected_classical_registers_sorted)
+}
+
+/// Packed-key counterpart of `purity_cell_rust` for the echo/overlap path:
+/// both count tables are grouped into the same subsystem, then the pairwise
+/// ensemble weight runs across the two groups.
+#[pyfunction]
+#[pyo3(signature = (idx, first_counts, second_counts, bit_string_range, subsystem_size))]
+pub fn echo_cell_rust(
+    idx: i32,
+    first_counts: HashMap<String, i32>,
+    second_counts: HashMap<String, i32>,
+    bit_string_range: (i32, i32),
+    subsystem_size: i32,
+) -> (i32, f64) {
+    echo_cell_impl(idx, &first_counts, &second_counts, bit_string_range, subsystem_size)
+}
+
+/// Borrowing counterpart of `echo_cell_rust`, used by the `overlap_echo_core_*`
+/// dispatchers so each cell borrows into the shared input `counts` vector
+/// instead of cloning its two `HashMap`s before handing them to a worker.
+pub fn echo_cell_impl(
+    idx: i32,
+    first_counts: &HashMap<String, i32>,
+    second_counts: &HashMap<String, i32>,
+    bit_string_range: (i32, i32),
+    subsystem_size: i32,
+) -> (i32, f64) {
+    let shots: i32 = first_counts.values().sum();
+    let shots2: i32 = second_counts.values().sum();
+    assert_eq!(
+        shots, shots2,
+        "The number of shots must be equal, count1: {}, count2: {}",
+        shots, shots2
+    );
+
+    let pack_group = |counts: &HashMap<String, i32>| -> AHashMap<u128, i32> {
+        let mut grouped: AHashMap<u128, i32> = AHashMap::with_capacity(counts.len());
+        if 0 <= bit_string_range.0 && bit_string_range.1 <= subsystem_size {
+            for (bit_string, count) in counts {
+                let substring =
+                    &bit_string[bit_string_range.0 as usize..bit_string_range.1 as usize];
+                let packed = pack_bitstring_rust(substring).unwrap_or_else(|err| panic!("Error: {}", err));
+                *grouped.entry(packed).or_insert(0) += count;
+            }
+        } else {
+            for (bit_string, count) in counts {
+                let substring =
+                    cycling_slice_rust(bit_string, bit_string_range.0, bit_string_range.1, 1)
+                        .unwrap_or_else(|err| panic!("Error: {}", err));
+                let packed = pack_bitstring_rust(&substring).unwrap_or_else(|err| panic!("Error: {}", err));
+                *grouped.entry(packed).or_insert(0) += count;
+            }
+        }
+        grouped
+    };
+
+    let first_under_degree = pack_group(first_counts);
+    let second_under_degree = pack_group(second_counts);
+
+    let echo_cell: f64 = first_under_degree
+        .par_iter()
+        .flat_map(|(s_ai, s_ai_meas)| {
+            second_under_degree.par_iter().map(move |(s_aj, s_aj_meas)| {
+                ensemble_cell_packed_rust(*s_ai, *s_ai_meas, *s_aj, *s_aj_meas, subsystem_size, shots)
+            })
+        })
+        .sum();
+
+    (idx, echo_cell)
+}
+
+/// Packed-key counterpart of `purity_cell_2_rust` for the echo/overlap path.
+#[pyfunction]
+#[pyo3(signature = (idx, first_counts, second_counts, selected_classical_registers))]
+pub fn echo_cell_2_rust(
+    idx: i32,
+    first_counts: HashMap<String, i32>,
+    second_counts: HashMap<String, i32>,
+    selected_classical_registers: Vec<i32>,
+) -> (i32, f64, Vec<i32>) {
+    echo_cell_2_impl(idx, &first_counts, &second_counts, selected_classical_registers)
+}
+
+/// Borrowing counterpart of `echo_cell_2_rust`.
+pub fn echo_cell_2_impl(
+    idx: i32,
+    first_counts: &HashMap<String, i32>,
+    second_counts: &HashMap<String, i32>,
+    selected_classical_registers: Vec<i32>,
+) -> (i32, f64, Vec<i32>) {
+    let sample_shots_01: i32 = first_counts.values().sum();
+    let sample_shots_02: i32 = second_counts.values().sum();
+    assert_eq!(
+        sample_shots_01, sample_shots_02,
+        "The number of shots must be equal, but the first count is {}, and the second count is {}, in the index {}",
+        sample_shots_01, sample_shots_02, idx,
+    );
+
+    let mut selected_classical_registers_sorted = selected_classical_registers.clone();
+    selected_classical_registers_sorted.sort();
+    let subsystem_size = selected_classical_registers_sorted.len() as i32;
+
+    let pack_group = |counts: &HashMap<String, i32>| -> AHashMap<u128, i32> {
+        let mut grouped: AHashMap<u128, i32> = AHashMap::with_capacity(counts.len());
+        for (bit_string_all, count) in counts {
+            let packed = gather_bits_from_str(bit_string_all, &selected_classical_registers)
+                .unwrap_or_else(|err| panic!("Error: {}", err));
+            *grouped.entry(packed).or_insert(0) += count;
+        }
+        grouped
+    };
+
+    let first_under_degree = pack_group(first_counts);
+    let second_under_degree = pack_group(second_counts);
+
+    let echo_cell: f64 = first_under_degree
+        .par_iter()
+        .flat_map(|(s_ai, s_ai_meas)| {
+            second_under_degree.par_iter().map(move |(s_aj, s_aj_meas)| {
+                ensemble_cell_packed_rust(*s_ai, *s_ai_meas, *s_aj, *s_aj_meas, subsystem_size, sample_shots_01)
+            })
+        })
+        .sum();
+
+    (idx, echo_cell, selected_classical_registers_sorted)
+}
@@ -0,0 +1,103 @@
+extern crate crossbeam_channel;
+extern crate pyo3;
+extern crate serde_json;
+
+use pyo3::exceptions::PyIOError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::randomized::construct::{degree_handler_rust, QubitDegree};
+use crate::randomized::randomized::purity_cell_rust;
+
+/// Out-of-core variant of `entangled_entropy_core_rust` for counts that do
+/// not fit in memory all at once.
+///
+/// A single producer thread reads `path` one newline-delimited JSON count
+/// map at a time and sends it into a `channel_capacity`-bounded
+/// `crossbeam_channel`, capping the number of in-flight maps to roughly
+/// `channel_capacity` regardless of file size. A pool of worker threads
+/// pulls maps off the channel, runs `purity_cell_rust`, and accumulates into
+/// a running sum/count instead of a full loader `HashMap`. Returns the same
+/// `(mean_purity, bitstring_range, actual_measure, duration)` shape as the
+/// in-memory `entangled_entropy_core_mean_rust`.
+#[pyfunction]
+#[pyo3(signature = (shots, path, degree=None, measure=None, channel_capacity=64, num_workers=4))]
+pub fn entangled_entropy_core_stream_rust(
+    shots: i32,
+    path: &str,
+    degree: Option<QubitDegree>,
+    measure: Option<(i32, i32)>,
+    channel_capacity: usize,
+    num_workers: usize,
+) -> PyResult<(f64, (i32, i32), (i32, i32), f64)> {
+    let file = File::open(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let first_line = lines
+        .next()
+        .ok_or_else(|| PyIOError::new_err(format!("{} is empty", path)))?
+        .map_err(|err| PyIOError::new_err(err.to_string()))?;
+    let first_counts: HashMap<String, i32> = serde_json::from_str(&first_line)
+        .map_err(|err| PyIOError::new_err(err.to_string()))?;
+
+    let sample_shots: i32 = first_counts.values().sum();
+    assert!(shots == sample_shots);
+
+    let allsystems_size: i32 = first_counts.keys().next().unwrap().len() as i32;
+    let (bitstring_range, actual_measure, subsystems_size) =
+        degree_handler_rust(allsystems_size, degree, measure);
+
+    let begin: Instant = Instant::now();
+
+    let (sender, receiver) = crossbeam_channel::bounded::<HashMap<String, i32>>(channel_capacity);
+    let accumulator = Arc::new(Mutex::new((0.0_f64, 0_i32)));
+
+    let workers: Vec<_> = (0..num_workers)
+        .map(|_| {
+            let receiver = receiver.clone();
+            let accumulator = Arc::clone(&accumulator);
+            thread::spawn(move || {
+                let mut local_sum = 0.0_f64;
+                let mut local_count = 0_i32;
+                for (idx, data) in receiver.iter().enumerate() {
+                    let (_, purity) =
+                        purity_cell_rust(idx as i32, data, bitstring_range, subsystems_size);
+                    local_sum += purity;
+                    local_count += 1;
+                }
+                let mut totals = accumulator.lock().unwrap();
+                totals.0 += local_sum;
+                totals.1 += local_count;
+            })
+        })
+        .collect();
+
+    sender
+        .send(first_counts)
+        .expect("the worker pool outlives the producer");
+    for line in lines {
+        let line = line.map_err(|err| PyIOError::new_err(err.to_string()))?;
+        let data: HashMap<String, i32> =
+            serde_json::from_str(&line).map_err(|err| PyIOError::new_err(err.to_string()))?;
+        sender
+            .send(data)
+            .expect("the worker pool outlives the producer");
+    }
+    drop(sender);
+
+    for worker in workers {
+        worker.join().expect("worker thread panicked");
+    }
+
+    let (sum, count) = *accumulator.lock().unwrap();
+    let mean_purity = sum / (count as f64);
+
+    let duration: f64 = begin.elapsed().as_secs_f64();
+
+    Ok((mean_purity, bitstring_range, actual_measure, duration))
+}
@@ -1,15 +1,139 @@
+extern crate blake3;
 extern crate pyo3;
+extern crate rand_chacha;
+extern crate rand_pcg;
 
 use dashmap::DashMap;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rand::Rng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rand_pcg::Pcg64Mcg;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Build a `ChaCha8Rng` for one filler decision: `seed_from_u64` when `seed`
+/// is set (mixed with `tag`/`index` so parallel iterations over distinct
+/// indices draw from independent streams), or entropy seeding otherwise so
+/// the unseeded behavior is unchanged from the old `thread_rng()` calls.
+fn filler_rng(seed: Option<u64>, tag: u64, index: u64) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed ^ tag ^ index),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
+/// Build a `Pcg64Mcg` for one filler decision, mixing `tag`/`index` into the
+/// seed the same way `filler_rng` does. Callers that amortize construction
+/// across a fixed-size chunk (see `fill_in_chunks`) pass a chunk index
+/// rather than a per-element index here, which keeps "same seed, same
+/// output" independent of thread count/scheduler: a `map_init`-reused
+/// generator, by contrast, is initialized once per rayon *split*, and split
+/// boundaries shift with the thread count, so two elements at the same
+/// offset in different splits could draw different "random" decisions
+/// between runs.
+fn worker_rng(seed: Option<u64>, tag: u64, index: u64) -> Pcg64Mcg {
+    match seed {
+        Some(seed) => Pcg64Mcg::seed_from_u64(seed ^ tag ^ index),
+        None => Pcg64Mcg::from_entropy(),
+    }
+}
+
+/// Fixed size of the chunks `fill_in_chunks` hands to one worker RNG, chosen
+/// independently of thread count so chunk boundaries (and thus the seed each
+/// chunk draws) never shift with the scheduler.
+const FILLER_CHUNK_SIZE: usize = 1024;
+
+/// Fill `items` in fixed-size chunks, constructing one `worker_rng_at`
+/// seeded RNG per chunk (keyed on `tag` and the chunk's index, not rayon's
+/// internal split boundaries) and reusing it across every element in that
+/// chunk, rather than constructing a fresh RNG per element. This keeps the
+/// "same seed, same output regardless of thread count" guarantee `filler_rng`
+/// already has for single-shot decisions, while still amortizing RNG
+/// construction over `FILLER_CHUNK_SIZE` elements instead of paying it once
+/// per element.
+fn fill_in_chunks<T, WR, WF, F>(items: &[T], tag: u64, worker_rng_at: &WF, fill_one: F) -> Vec<String>
+where
+    T: Sync,
+    WR: Rng,
+    WF: Fn(u64, u64) -> WR + Sync,
+    F: Fn(&mut WR, &T) -> String + Sync,
+{
+    items
+        .par_chunks(FILLER_CHUNK_SIZE)
+        .enumerate()
+        .flat_map(|(chunk_index, chunk)| {
+            let mut rng = worker_rng_at(tag, chunk_index as u64);
+            chunk
+                .iter()
+                .map(|item| fill_one(&mut rng, item))
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Key-addressable replacement for a PRNG: every `Rng` call reads the next
+/// bytes off a BLAKE3 keyed XOF stream instead of advancing seed state, so
+/// the same `(key, tag, index)` always produces the same decisions on any
+/// machine, and the dataset never needs to be stored (just the key).
+struct XofRng(blake3::OutputReader);
+
+impl XofRng {
+    /// `tag` distinguishes the filler loop/round a decision comes from and
+    /// `index` distinguishes the element within that loop, mirroring how
+    /// `filler_rng`/`worker_rng` mix the same two values into a seed.
+    fn new(key: &[u8; 32], tag: u64, index: u64) -> Self {
+        let mut hasher = blake3::Hasher::new_keyed(key);
+        hasher.update(&tag.to_le_bytes());
+        hasher.update(&index.to_le_bytes());
+        XofRng(hasher.finalize_xof())
+    }
+}
+
+impl RngCore for XofRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.0.fill(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.0.fill(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.fill(dest);
+        Ok(())
+    }
+}
+
+/// Hash an arbitrary caller-supplied key string down to the 32 bytes
+/// `blake3::Hasher::new_keyed` requires.
+fn derive_xof_key(key: &str) -> [u8; 32] {
+    *blake3::hash(key.as_bytes()).as_bytes()
+}
+
+/// Shared by both the seeded (`filler_rng`/`worker_rng`) and keyed
+/// (`XofRng`) filler paths: flip one fair coin to decide whether `ff`
+/// prefixes or suffixes `item`.
+fn filler_h_or_e<R: Rng + ?Sized>(rng: &mut R, ff: &str, item: &str) -> String {
+    if rng.gen::<bool>() {
+        format!("{}{}", ff, item)
+    } else {
+        format!("{}{}", item, ff)
+    }
+}
+
 fn generate_bits(num: usize, bits: Option<Arc<Vec<String>>>) -> Arc<Vec<String>> {
     let bits = bits.unwrap_or_else(|| Arc::new(vec![String::new()]));
 
@@ -25,9 +149,28 @@ fn generate_bits(num: usize, bits: Option<Arc<Vec<String>>>) -> Arc<Vec<String>>
     generate_bits(num - 1, Some(Arc::new(new_bits)))
 }
 
-#[pyfunction]
-#[pyo3(signature = (bitlen, num=None))]
-pub fn make_two_bit_str_32(bitlen: usize, num: Option<usize>) -> PyResult<Vec<String>> {
+/// Shared filler algorithm behind `make_two_bit_str_32`'s seeded and
+/// BLAKE3-keyed modes: builds the requested `bitlen`-long bitstrings by
+/// generating a `logged_num`-bit raw block and recursively padding it out
+/// with random fillers, abstracting over *how* one decision's RNG is
+/// produced. `quality_rng_at(tag, index)` backs the low-frequency single
+/// draws (the initial shuffle/first-filler pick); `worker_rng_at(tag,
+/// index)` backs the four hot per-element filler loops. Both take the same
+/// `(tag, index)` pair `filler_rng`/`worker_rng`/`XofRng::new` already mix
+/// into a seed, so the two call sites below only need to supply which RNG
+/// type answers that pair.
+fn make_two_bit_str_32_with<QR, WR, QF, WF>(
+    bitlen: usize,
+    num: Option<usize>,
+    quality_rng_at: QF,
+    worker_rng_at: WF,
+) -> PyResult<Vec<String>>
+where
+    QR: Rng,
+    WR: Rng,
+    QF: Fn(u64, u64) -> QR,
+    WF: Fn(u64, u64) -> WR + Sync,
+{
     const ULTMAX: usize = 31;
     let mut is_less_than_16 = false;
     let mut less_slice = 0;
@@ -74,7 +217,7 @@ pub fn make_two_bit_str_32(bitlen: usize, num: Option<usize>) -> PyResult<Vec<St
     if bitlen <= logged_num as usize {
         let mut result = generate_bits(bitlen);
         if is_less_than_16 {
-            result.shuffle(&mut thread_rng());
+            result.shuffle(&mut quality_rng_at(0, 0));
             return Ok(result[..less_slice].to_vec());
         }
         return Ok(result);
@@ -87,44 +230,40 @@ pub fn make_two_bit_str_32(bitlen: usize, num: Option<usize>) -> PyResult<Vec<St
     assert_eq!(2_usize.pow(logged_num as u32), len_raw_content);
     assert!(2 * len_raw_content >= real_num && real_num >= len_raw_content);
 
-    let mut rng = rand::thread_rng();
+    let mut rng = quality_rng_at(1, 0);
     let first_filler = if rng.gen::<bool>() {
         vec!["0", "1"]
     } else {
         vec!["1", "0"]
     };
 
-    fn filler_h_or_e(ff: &str, item: &str) -> String {
-        if rand::thread_rng().gen::<bool>() {
-            format!("{}{}", ff, item)
-        } else {
-            format!("{}{}", item, ff)
-        }
-    }
+    let mut num_fulfill_content: Vec<String> = fill_in_chunks(&raw_content, 2, &worker_rng_at, |rng, item| {
+        filler_h_or_e(rng, first_filler[0], item)
+    });
 
-    let mut num_fulfill_content: Vec<String> = raw_content
-        .par_iter()
-        .map(|item| filler_h_or_e(first_filler[0], item))
-        .collect();
-
-    let remaining_items: Vec<String> = raw_content[..(real_num - len_raw_content)]
-        .par_iter()
-        .map(|item| filler_h_or_e(first_filler[1], item))
-        .collect();
+    let remaining_items: Vec<String> = fill_in_chunks(
+        &raw_content[..(real_num - len_raw_content)],
+        3,
+        &worker_rng_at,
+        |rng, item| filler_h_or_e(rng, first_filler[1], item),
+    );
 
     num_fulfill_content.extend(remaining_items);
 
     let mut less_bitlen = less_bitlen;
+    let mut round: u64 = 0;
     while less_bitlen >= logged_num as usize {
-        num_fulfill_content = num_fulfill_content
-            .par_iter()
-            .map(|item| {
-                let mut rng = rand::thread_rng();
+        num_fulfill_content = fill_in_chunks(
+            &num_fulfill_content,
+            4 ^ (round << 32),
+            &worker_rng_at,
+            |rng, item| {
                 let rand_item = &raw_content[rng.gen_range(0..len_raw_content)];
-                filler_h_or_e(rand_item, item)
-            })
-            .collect();
+                filler_h_or_e(rng, rand_item, item)
+            },
+        );
         less_bitlen -= logged_num as usize;
+        round += 1;
     }
 
     if less_bitlen == 0 {
@@ -137,36 +276,65 @@ pub fn make_two_bit_str_32(bitlen: usize, num: Option<usize>) -> PyResult<Vec<St
     let remain_fillers = generate_bits(less_bitlen);
     let len_remain_fillers = remain_fillers.len();
 
-    let mut result: Vec<String> = num_fulfill_content
-        .par_iter()
-        .map(|item| {
-            let mut rng = rand::thread_rng();
-            let filler = &remain_fillers[rng.gen_range(0..len_remain_fillers)];
-            filler_h_or_e(filler, item)
-        })
-        .collect();
+    let mut result: Vec<String> = fill_in_chunks(&num_fulfill_content, 5, &worker_rng_at, |rng, item| {
+        let filler = &remain_fillers[rng.gen_range(0..len_remain_fillers)];
+        filler_h_or_e(rng, filler, item)
+    });
 
     if is_less_than_16 {
-        result.shuffle(&mut thread_rng());
+        result.shuffle(&mut quality_rng_at(6, 0));
         return Ok(result[..less_slice].to_vec());
     }
     Ok(result)
 }
 
 #[pyfunction]
-#[pyo3(signature = (num))]
-pub fn make_two_bit_str_unlimit(num: usize) -> Vec<String> {
+#[pyo3(signature = (bitlen, num=None, seed=None, key=None))]
+pub fn make_two_bit_str_32(
+    bitlen: usize,
+    num: Option<usize>,
+    seed: Option<u64>,
+    key: Option<String>,
+) -> PyResult<Vec<String>> {
+    match key {
+        Some(key) => {
+            let key = derive_xof_key(&key);
+            make_two_bit_str_32_with(
+                bitlen,
+                num,
+                |tag, index| XofRng::new(&key, tag, index),
+                |tag, index| XofRng::new(&key, tag, index),
+            )
+        }
+        None => make_two_bit_str_32_with(
+            bitlen,
+            num,
+            |tag, index| filler_rng(seed, tag, index),
+            |tag, index| worker_rng(seed, tag, index),
+        ),
+    }
+}
+
+/// `num` enumerates every bitstring exhaustively rather than sampling, so
+/// there is no randomness to seed; `seed` is accepted anyway for signature
+/// parity with `make_two_bit_str_32`/`make_dummy_case_32`.
+#[pyfunction]
+#[pyo3(signature = (num, seed=None))]
+#[allow(unused_variables)]
+pub fn make_two_bit_str_unlimit(num: usize, seed: Option<u64>) -> Vec<String> {
     Arc::try_unwrap(generate_bits(num, None)).unwrap_or_else(|arc| (*arc).clone())
 }
 
 #[pyfunction]
-#[pyo3(signature = (n_a, shot_per_case, bitstring_num=None))]
+#[pyo3(signature = (n_a, shot_per_case, bitstring_num=None, seed=None, key=None))]
 pub fn make_dummy_case_32(
     n_a: usize,
     shot_per_case: usize,
     bitstring_num: Option<usize>,
+    seed: Option<u64>,
+    key: Option<String>,
 ) -> PyResult<HashMap<String, usize>> {
-    let raw_bitstring_cases = make_two_bit_str_32(n_a, bitstring_num);
+    let raw_bitstring_cases = make_two_bit_str_32(n_a, bitstring_num, seed, key);
     let bitstring_cases = match raw_bitstring_cases {
         Ok(cases) => cases,
         Err(_) => {